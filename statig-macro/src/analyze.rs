@@ -0,0 +1,233 @@
+//! Analysis pass: turn the annotated `impl` block into a hierarchy model.
+//!
+//! The model built here is shared by every code-generation fragment
+//! ([`crate::codegen`], [`crate::tables`], [`crate::dot`], [`crate::parallel`])
+//! so the emitted enums, lookup tables and diagram can never drift apart.
+
+use syn::{Attribute, Ident, ImplItemFn, ItemImpl};
+
+/// The analyzed state machine.
+pub struct Machine {
+    /// The shared-storage type the macro was applied to.
+    pub ident: Ident,
+    /// Every leaf state, in declaration (and index) order.
+    pub states: Vec<Handler>,
+    /// Every superstate, in declaration order.
+    pub superstates: Vec<Handler>,
+}
+
+/// A single `#[state]` or `#[superstate]` handler function.
+pub struct Handler {
+    /// The handler function name, which is also the variant name.
+    pub name: Ident,
+    /// The name of the containing superstate, if any (`superstate = "..."`).
+    pub superstate: Option<Ident>,
+    /// The regions declared by `#[state(parallel = [...])]`, if any.
+    pub parallel: Vec<Ident>,
+    /// Transition targets inferred from the handler body, with the event
+    /// variant that triggers them when statically determinable.
+    pub edges: Vec<Edge>,
+}
+
+/// An inferred transition edge.
+pub struct Edge {
+    pub target: Ident,
+    pub label: Option<String>,
+    pub kind: EdgeKind,
+}
+
+pub enum EdgeKind {
+    Transition,
+    Push,
+    Pop,
+}
+
+impl Machine {
+    /// Analyze the annotated `impl` block.
+    pub fn analyze(item: &ItemImpl) -> syn::Result<Self> {
+        let ident = match &*item.self_ty {
+            syn::Type::Path(path) => path.path.segments.last().unwrap().ident.clone(),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "state_machine must be applied to an inherent impl of a named type",
+                ))
+            }
+        };
+
+        let mut states = Vec::new();
+        let mut superstates = Vec::new();
+
+        for item in &item.items {
+            if let syn::ImplItem::Fn(function) = item {
+                if let Some(attr) = find_attr(&function.attrs, "state") {
+                    states.push(Handler::parse(function, attr)?);
+                } else if let Some(attr) = find_attr(&function.attrs, "superstate") {
+                    superstates.push(Handler::parse(function, attr)?);
+                }
+            }
+        }
+
+        // Every `superstate = "..."` link must name a declared superstate.
+        for handler in states.iter().chain(&superstates) {
+            if let Some(parent) = &handler.superstate {
+                if !superstates.iter().any(|superstate| &superstate.name == parent) {
+                    return Err(syn::Error::new_spanned(parent, "unknown superstate"));
+                }
+            }
+        }
+
+        Ok(Self {
+            ident,
+            states,
+            superstates,
+        })
+    }
+
+    /// The depth of a handler: one more than its superstate's depth, or `1` for
+    /// a direct child of the implicit `top`.
+    pub fn depth(&self, handler: &Handler) -> usize {
+        match &handler.superstate {
+            Some(parent) => self.depth(self.lookup(parent)) + 1,
+            None => 1,
+        }
+    }
+
+    /// Find a handler (state or superstate) by name.
+    pub fn lookup(&self, name: &Ident) -> &Handler {
+        self.states
+            .iter()
+            .chain(&self.superstates)
+            .find(|handler| &handler.name == name)
+            .expect("containment links are validated during analysis")
+    }
+}
+
+impl Handler {
+    fn parse(function: &ImplItemFn, attr: &Attribute) -> syn::Result<Self> {
+        let mut superstate = None;
+        let mut parallel = Vec::new();
+
+        // Parse `superstate = "..."` and `parallel = ["a", "b"]` from the
+        // attribute's meta list, tolerating either being absent.
+        if matches!(attr.meta, syn::Meta::List(_)) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("superstate") {
+                    let value = meta.value()?.parse::<syn::LitStr>()?;
+                    superstate = Some(value.parse()?);
+                } else if meta.path.is_ident("parallel") {
+                    meta.value()?.parse::<syn::ExprArray>()?.elems.iter().try_for_each(
+                        |elem| -> syn::Result<()> {
+                            if let syn::Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Str(name),
+                                ..
+                            }) = elem
+                            {
+                                parallel.push(name.parse()?);
+                            }
+                            Ok(())
+                        },
+                    )?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(Self {
+            name: function.sig.ident.clone(),
+            superstate,
+            parallel,
+            edges: infer_edges(function),
+        })
+    }
+}
+
+/// Find an attribute by path name.
+fn find_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs.iter().find(|attr| attr.path().is_ident(name))
+}
+
+/// Scan a handler body for `Transition`/`Push`/`Pop` expressions to infer the
+/// machine's transition edges, labeling each with the matched event variant
+/// where it is statically determinable.
+fn infer_edges(function: &ImplItemFn) -> Vec<Edge> {
+    let mut visitor = EdgeVisitor::default();
+    syn::visit::Visit::visit_block(&mut visitor, &function.block);
+    visitor.edges
+}
+
+#[derive(Default)]
+struct EdgeVisitor {
+    edges: Vec<Edge>,
+    /// The event variant currently in scope via a `match` arm, if any.
+    current_label: Option<String>,
+}
+
+impl<'ast> syn::visit::Visit<'ast> for EdgeVisitor {
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        // Remember the event variant this arm matches so edges found inside it
+        // can be labeled with it.
+        let previous = self.current_label.take();
+        self.current_label = event_variant(&arm.pat);
+        syn::visit::visit_arm(self, arm);
+        self.current_label = previous;
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = &*call.func {
+            if let Some(ident) = path.path.segments.last() {
+                let kind = match ident.ident.to_string().as_str() {
+                    "Transition" => Some(EdgeKind::Transition),
+                    "Push" => Some(EdgeKind::Push),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    if let Some(target) = call.args.first().and_then(state_target) {
+                        self.edges.push(Edge {
+                            target,
+                            label: self.current_label.clone(),
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_path(&mut self, path: &'ast syn::ExprPath) {
+        if path.path.is_ident("Pop") {
+            // A `Pop` edge has no explicit target; record it for the diagram.
+            self.edges.push(Edge {
+                target: Ident::new("Pop", path.path.segments[0].ident.span()),
+                label: self.current_label.clone(),
+                kind: EdgeKind::Pop,
+            });
+        }
+        syn::visit::visit_expr_path(self, path);
+    }
+}
+
+/// Extract the target state constructor name from a `State::foo()` argument.
+fn state_target(expr: &syn::Expr) -> Option<Ident> {
+    if let syn::Expr::Call(call) = expr {
+        if let syn::Expr::Path(path) = &*call.func {
+            return path.path.segments.last().map(|segment| segment.ident.clone());
+        }
+    }
+    None
+}
+
+/// Extract the event variant name from a match pattern like `Event::Foo`.
+fn event_variant(pat: &syn::Pat) -> Option<String> {
+    match pat {
+        syn::Pat::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        syn::Pat::TupleStruct(tuple) => {
+            tuple.path.segments.last().map(|s| s.ident.to_string())
+        }
+        syn::Pat::Struct(structure) => {
+            structure.path.segments.last().map(|s| s.ident.to_string())
+        }
+        _ => None,
+    }
+}
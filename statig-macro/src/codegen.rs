@@ -0,0 +1,261 @@
+//! Code generation for the `State`/`Superstate` enums and their trait impls.
+//!
+//! This emits the awaitable flavour used by this chunk; the tables and
+//! `index()` bodies are spliced in from [`crate::tables`] so the integer-walk
+//! LCA in the driver has its lookup data.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Expr, FnArg, Ident, ImplItemFn, Pat};
+
+use crate::analyze::{Handler, Machine};
+use crate::tables::{self, Node};
+
+/// Configuration parsed from the `state_machine(...)` attribute arguments.
+pub struct Config {
+    pub initial: Expr,
+    pub on_transition: Option<Expr>,
+    pub on_dispatch: Option<Expr>,
+    pub on_pause: Option<Expr>,
+    pub on_resume: Option<Expr>,
+}
+
+/// Emit the full expansion for the analyzed machine.
+pub fn expand(machine: &Machine, config: &Config, handlers: &[ImplItemFn]) -> TokenStream {
+    let state_enum = emit_state_enum(machine);
+    let superstate_enum = emit_superstate_enum(machine);
+    let into_state_machine = emit_into_state_machine(machine, config);
+    let state_impl = emit_state_impl(machine, handlers);
+
+    quote! {
+        #state_enum
+        #superstate_enum
+        #into_state_machine
+        #state_impl
+    }
+}
+
+/// Build the stable-index node list consumed by the table emitter.
+fn nodes(machine: &Machine) -> Vec<Node> {
+    // States come first, then superstates; the index is the position here.
+    let ordered: Vec<(&Handler, bool)> = machine
+        .states
+        .iter()
+        .map(|handler| (handler, true))
+        .chain(machine.superstates.iter().map(|handler| (handler, false)))
+        .collect();
+
+    let index_of = |name: &Ident| ordered.iter().position(|(h, _)| &h.name == name);
+
+    ordered
+        .iter()
+        .map(|(handler, is_state)| {
+            let variant = variant_ident(&handler.name);
+            let pattern = if *is_state {
+                quote!(State::#variant)
+            } else {
+                quote!(Superstate::#variant)
+            };
+            Node {
+                pattern,
+                is_state: *is_state,
+                depth: machine.depth(handler),
+                parent: handler.superstate.as_ref().and_then(|name| index_of(name)),
+            }
+        })
+        .collect()
+}
+
+fn emit_state_enum(machine: &Machine) -> TokenStream {
+    let variants = machine.states.iter().map(|handler| variant_ident(&handler.name));
+    let constructors = machine.states.iter().map(|handler| {
+        let name = &handler.name;
+        let variant = variant_ident(name);
+        quote! {
+            #[allow(non_snake_case)]
+            pub fn #name() -> Self {
+                State::#variant
+            }
+        }
+    });
+
+    quote! {
+        pub enum State {
+            #(#variants),*
+        }
+
+        impl State {
+            #(#constructors)*
+        }
+    }
+}
+
+fn emit_superstate_enum(machine: &Machine) -> TokenStream {
+    let variants = machine
+        .superstates
+        .iter()
+        .map(|handler| variant_ident(&handler.name));
+
+    quote! {
+        pub enum Superstate {
+            #(#variants),*
+        }
+    }
+}
+
+fn emit_into_state_machine(machine: &Machine, config: &Config) -> TokenStream {
+    let ident = &machine.ident;
+    let initial = &config.initial;
+    let tables = tables::emit(&nodes(machine));
+    let consts = &tables.consts;
+
+    // Each hook is emitted only when the user supplied it; otherwise the trait's
+    // no-op default stands.
+    let state_pair = quote!(&Self::State, &Self::State);
+    let on_transition = hook_const("ON_TRANSITION", &config.on_transition, &state_pair);
+    let on_pause = hook_const("ON_PAUSE", &config.on_pause, &state_pair);
+    let on_resume = hook_const("ON_RESUME", &config.on_resume, &state_pair);
+    let on_dispatch = hook_const(
+        "ON_DISPATCH",
+        &config.on_dispatch,
+        &quote!(::statig::StateOrSuperstate<Self>, &Self::Event<'_>),
+    );
+
+    quote! {
+        impl ::statig::IntoStateMachine for #ident {
+            type State = State;
+            type Superstate<'a> = Superstate;
+            type Event<'a> = Event;
+            type Context<'a> = ();
+
+            const INITIAL: State = #initial;
+            #consts
+            #on_transition
+            #on_dispatch
+            #on_pause
+            #on_resume
+        }
+    }
+}
+
+fn hook_const(name: &str, expr: &Option<Expr>, params: &TokenStream) -> TokenStream {
+    match expr {
+        Some(expr) => {
+            let name = format_ident!("{}", name);
+            quote!(const #name: fn(&mut Self, #params) = #expr;)
+        }
+        None => quote!(),
+    }
+}
+
+fn emit_state_impl(machine: &Machine, handlers: &[ImplItemFn]) -> TokenStream {
+    let ident = &machine.ident;
+    let tables = tables::emit(&nodes(machine));
+    let state_index = &tables.state_index;
+    let superstate_index = &tables.superstate_index;
+
+    let state_arms = machine.states.iter().map(|handler| {
+        let variant = variant_ident(&handler.name);
+        if handler.parallel.is_empty() {
+            let call = call_handler(ident, handler, handlers);
+            quote!(State::#variant => #call)
+        } else {
+            // A parallel state has no handler body of its own: dispatch the
+            // event to its orthogonal regions via the generated broadcast.
+            let dispatch = format_ident!("{}_parallel_dispatch", &handler.name);
+            quote!(State::#variant => shared_storage.#dispatch(event).await)
+        }
+    });
+
+    let superstate_links = machine.states.iter().map(|handler| {
+        let variant = variant_ident(&handler.name);
+        match &handler.superstate {
+            Some(parent) => {
+                let parent = variant_ident(parent);
+                quote!(State::#variant => Some(Superstate::#parent))
+            }
+            None => quote!(State::#variant => None),
+        }
+    });
+
+    quote! {
+        impl ::statig::awaitable::State<#ident> for State {
+            async fn call_handler(
+                &mut self,
+                shared_storage: &mut #ident,
+                event: &Event,
+                context: &mut (),
+            ) -> ::statig::Response<State> {
+                let _ = context;
+                match self {
+                    #(#state_arms),*
+                }
+            }
+
+            async fn call_entry_action(&mut self, _: &mut #ident, _: &mut ()) {}
+
+            async fn call_exit_action(&mut self, _: &mut #ident, _: &mut ()) {}
+
+            fn superstate(&mut self) -> Option<Superstate> {
+                match self {
+                    #(#superstate_links),*
+                }
+            }
+
+            fn index(&self) -> usize {
+                #state_index
+            }
+        }
+
+        impl ::statig::awaitable::Superstate<#ident> for Superstate {
+            fn index(&self) -> usize {
+                #superstate_index
+            }
+        }
+    }
+}
+
+/// Emit the call to a user handler, forwarding `event`/`context`/`self` by the
+/// parameter names the handler declares.
+fn call_handler(ident: &Ident, handler: &Handler, handlers: &[ImplItemFn]) -> TokenStream {
+    let name = &handler.name;
+    let function = handlers.iter().find(|f| f.sig.ident == *name);
+
+    let mut takes_self = false;
+    let mut args = Vec::new();
+    if let Some(function) = function {
+        for input in &function.sig.inputs {
+            match input {
+                FnArg::Receiver(_) => takes_self = true,
+                FnArg::Typed(typed) => {
+                    if let Pat::Ident(pat) = &*typed.pat {
+                        let arg = &pat.ident;
+                        args.push(quote!(#arg));
+                    }
+                }
+            }
+        }
+    }
+
+    if takes_self {
+        quote!(shared_storage.#name(#(#args),*))
+    } else {
+        quote!(#ident::#name(#(#args),*))
+    }
+}
+
+/// Map a snake_case handler name to its PascalCase enum variant.
+fn variant_ident(name: &Ident) -> Ident {
+    let pascal: String = name
+        .to_string()
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    format_ident!("{}", pascal)
+}
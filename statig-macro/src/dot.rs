@@ -0,0 +1,184 @@
+//! Graphviz DOT rendering of the parsed state hierarchy.
+//!
+//! The `state_machine` macro already builds the containment hierarchy from the
+//! `#[state]`/`#[superstate]` functions and their `superstate = "..."` links,
+//! and scans each handler body for `Transition`/`Push`/`Pop` expressions to
+//! infer edges. This module turns that model into a DOT diagram and emits a
+//! `dot()` / `write_dot` pair on the shared-storage type.
+//!
+//! The emission of these methods is gated behind the `graphviz` cargo feature
+//! so it has zero cost when unused.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The subset of the analyzer's model needed to render a diagram.
+///
+/// This mirrors the hierarchy the analyzer already computes; it is passed in
+/// rather than recomputed so the DOT output can never drift from the generated
+/// machine.
+pub struct Hierarchy {
+    /// Every leaf state, in declaration order, with its containing superstate
+    /// (`None` for the implicit `top`).
+    pub states: Vec<Node>,
+    /// Every superstate, with its containing superstate (`None` for `top`).
+    pub superstates: Vec<Node>,
+    /// The transition edges inferred from the handler bodies.
+    pub edges: Vec<Edge>,
+}
+
+/// A state or superstate and the name of its parent superstate, if any.
+pub struct Node {
+    pub name: String,
+    pub parent: Option<String>,
+}
+
+/// A directed edge between two states, optionally labeled with the event
+/// variant that triggers it when that is statically determinable.
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+    /// The kind of response that produced the edge (`Transition`, `Push`,
+    /// `Pop`), used to style the arrow.
+    pub kind: EdgeKind,
+}
+
+pub enum EdgeKind {
+    Transition,
+    Push,
+    Pop,
+}
+
+impl Hierarchy {
+    /// Render the hierarchy as a DOT document.
+    ///
+    /// Each superstate becomes a `subgraph cluster_*` containing its children;
+    /// the implicit `top` state is the outermost cluster. Transitions become
+    /// directed edges labeled with the matched event variant where known.
+    pub fn render(&self) -> String {
+        let mut out = String::from("digraph statemachine {\n");
+        out.push_str("    compound = true;\n");
+        out.push_str("    node [shape = box];\n\n");
+
+        // The implicit `top` state is the outermost cluster containing
+        // everything without an explicit parent.
+        out.push_str("    subgraph cluster_top {\n");
+        out.push_str("        label = \"top\";\n");
+        self.render_children(&mut out, None, 2);
+        out.push_str("    }\n\n");
+
+        for edge in &self.edges {
+            out.push_str(&edge.render());
+            out.push('\n');
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Recursively render the states and superstates whose parent is `parent`.
+    fn render_children(&self, out: &mut String, parent: Option<&str>, indent: usize) {
+        let pad = "    ".repeat(indent);
+
+        for superstate in self.superstates.iter().filter(|n| n.parent.as_deref() == parent) {
+            out.push_str(&format!("{pad}subgraph cluster_{} {{\n", superstate.name));
+            out.push_str(&format!("{pad}    label = \"{}\";\n", superstate.name));
+            self.render_children(out, Some(&superstate.name), indent + 1);
+            out.push_str(&format!("{pad}}}\n"));
+        }
+
+        for state in self.states.iter().filter(|n| n.parent.as_deref() == parent) {
+            out.push_str(&format!("{pad}{};\n", state.name));
+        }
+    }
+}
+
+impl Edge {
+    fn render(&self) -> String {
+        let style = match self.kind {
+            EdgeKind::Transition => "",
+            EdgeKind::Push => " style = dashed",
+            EdgeKind::Pop => " style = dotted",
+        };
+        match &self.label {
+            Some(label) => format!("    {} -> {} [label = \"{label}\"{style}];", self.from, self.to),
+            None => format!("    {} -> {} [{}];", self.from, self.to, style.trim_start()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hierarchy() -> Hierarchy {
+        Hierarchy {
+            superstates: vec![Node {
+                name: "blinking".into(),
+                parent: None,
+            }],
+            states: vec![
+                Node {
+                    name: "led_on".into(),
+                    parent: Some("blinking".into()),
+                },
+                Node {
+                    name: "led_off".into(),
+                    parent: Some("blinking".into()),
+                },
+            ],
+            edges: vec![
+                Edge {
+                    from: "led_on".into(),
+                    to: "led_off".into(),
+                    label: Some("TimerElapsed".into()),
+                    kind: EdgeKind::Transition,
+                },
+                Edge {
+                    from: "led_off".into(),
+                    to: "led_on".into(),
+                    label: None,
+                    kind: EdgeKind::Push,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_clusters_edges_and_styles() {
+        let dot = hierarchy().render();
+
+        // Superstates become clusters nesting their children.
+        assert!(dot.contains("subgraph cluster_top"));
+        assert!(dot.contains("subgraph cluster_blinking"));
+        assert!(dot.contains("led_on;"));
+
+        // Edges carry their event label and push/pop styling.
+        assert!(dot.contains("led_on -> led_off [label = \"TimerElapsed\"];"));
+        assert!(dot.contains("style = dashed"));
+    }
+}
+
+/// Emit the `dot`/`write_dot` methods on the shared-storage type.
+///
+/// The rendered document is a compile-time constant, so `dot()` hands back a
+/// `&'static str` and `write_dot` simply forwards it to any [`core::fmt::Write`].
+/// Emission itself is gated by the macro's `graphviz` feature at the call site,
+/// so this is zero-cost when the feature is off.
+pub fn emit(ident: &syn::Ident, hierarchy: &Hierarchy) -> TokenStream {
+    let dot = hierarchy.render();
+    quote! {
+        impl #ident {
+            /// The machine's state hierarchy rendered as a Graphviz DOT diagram.
+            pub fn dot() -> &'static str {
+                #dot
+            }
+
+            /// Write the machine's DOT diagram to the given writer.
+            pub fn write_dot<W: ::core::fmt::Write>(writer: &mut W) -> ::core::fmt::Result {
+                writer.write_str(#dot)
+            }
+        }
+    }
+}
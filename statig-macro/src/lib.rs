@@ -0,0 +1,173 @@
+//! Procedural macro for the `statig` state machine library.
+//!
+//! `#[state_machine(...)]` parses the `#[state]`/`#[superstate]` handlers on an
+//! inherent `impl`, builds the containment hierarchy (see [`analyze`]) and emits
+//! the `State`/`Superstate` enums, the [`statig::IntoStateMachine`] impl with
+//! the precomputed depth/parent tables, and the handler dispatch. Behind the
+//! `graphviz` feature it also emits `dot()`/`write_dot` for the machine.
+
+mod analyze;
+mod codegen;
+mod dot;
+mod parallel;
+mod tables;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Expr, ImplItem, ImplItemFn, ItemImpl, LitStr, Meta, Token};
+
+use analyze::Machine;
+use codegen::Config;
+
+/// Generate a `statig` state machine from an annotated `impl` block.
+#[proc_macro_attribute]
+pub fn state_machine(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut item = syn::parse_macro_input!(input as ItemImpl);
+    let config = match parse_config(args) {
+        Ok(config) => config,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let machine = match Machine::analyze(&item) {
+        Ok(machine) => machine,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    // Collect the handler functions (with their attributes) for dispatch
+    // generation, then strip the `state`/`superstate` attributes from the impl
+    // we re-emit so they do not error as unknown attributes.
+    let handlers: Vec<ImplItemFn> = item
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(function) => Some(function.clone()),
+            _ => None,
+        })
+        .collect();
+    strip_handler_attrs(&mut item);
+
+    let codegen = codegen::expand(&machine, &config, &handlers);
+    let parallel = parallel::emit(&machine);
+
+    // DOT emission is gated by the macro's own `graphviz` feature.
+    #[cfg(feature = "graphviz")]
+    let dot = dot::emit(&machine.ident, &to_dot_hierarchy(&machine));
+    #[cfg(not(feature = "graphviz"))]
+    let dot = quote!();
+
+    quote! {
+        #item
+        #codegen
+        #parallel
+        #dot
+    }
+    .into()
+}
+
+/// Parse the `state_machine(...)` attribute arguments.
+fn parse_config(args: TokenStream) -> syn::Result<Config> {
+    let metas =
+        Punctuated::<Meta, Token![,]>::parse_terminated.parse(args)?;
+
+    let mut initial = None;
+    let mut on_transition = None;
+    let mut on_dispatch = None;
+    let mut on_pause = None;
+    let mut on_resume = None;
+
+    for meta in metas {
+        if let Meta::NameValue(pair) = meta {
+            // The string-literal form (`initial = "State::led_on()"`) is parsed
+            // into an expression; non-string values and list metas such as
+            // `state(derive(Debug))` are ignored.
+            let expr = match &pair.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(literal),
+                    ..
+                }) => Some(parse_lit_expr(literal)?),
+                _ => None,
+            };
+
+            if pair.path.is_ident("initial") {
+                initial = expr;
+            } else if pair.path.is_ident("on_transition") {
+                on_transition = expr;
+            } else if pair.path.is_ident("on_dispatch") {
+                on_dispatch = expr;
+            } else if pair.path.is_ident("on_pause") {
+                on_pause = expr;
+            } else if pair.path.is_ident("on_resume") {
+                on_resume = expr;
+            }
+        }
+    }
+
+    let initial = initial.ok_or_else(|| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "state_machine requires an `initial = \"...\"` argument",
+        )
+    })?;
+
+    Ok(Config {
+        initial,
+        on_transition,
+        on_dispatch,
+        on_pause,
+        on_resume,
+    })
+}
+
+fn parse_lit_expr(literal: &LitStr) -> syn::Result<Expr> {
+    literal.parse()
+}
+
+/// Remove the `state`/`superstate`/`action` attributes from the re-emitted impl.
+fn strip_handler_attrs(item: &mut ItemImpl) {
+    for item in &mut item.items {
+        if let ImplItem::Fn(function) = item {
+            function.attrs.retain(|attr| {
+                !attr.path().is_ident("state")
+                    && !attr.path().is_ident("superstate")
+                    && !attr.path().is_ident("action")
+            });
+        }
+    }
+}
+
+/// Project the analysis model onto the DOT renderer's hierarchy model.
+#[cfg(feature = "graphviz")]
+fn to_dot_hierarchy(machine: &Machine) -> dot::Hierarchy {
+    use analyze::EdgeKind;
+
+    let node = |handler: &analyze::Handler| dot::Node {
+        name: handler.name.to_string(),
+        parent: handler.superstate.as_ref().map(|name| name.to_string()),
+    };
+
+    let edges = machine
+        .states
+        .iter()
+        .chain(&machine.superstates)
+        .flat_map(|handler| {
+            handler.edges.iter().map(move |edge| dot::Edge {
+                from: handler.name.to_string(),
+                to: edge.target.to_string(),
+                label: edge.label.clone(),
+                kind: match edge.kind {
+                    EdgeKind::Transition => dot::EdgeKind::Transition,
+                    EdgeKind::Push => dot::EdgeKind::Push,
+                    EdgeKind::Pop => dot::EdgeKind::Pop,
+                },
+            })
+        })
+        .collect();
+
+    dot::Hierarchy {
+        states: machine.states.iter().map(node).collect(),
+        superstates: machine.superstates.iter().map(node).collect(),
+        edges,
+    }
+}
@@ -0,0 +1,58 @@
+//! Code generation for orthogonal (parallel) regions.
+//!
+//! A `#[state(parallel = ["region_a", "region_b"])]` state owns several child
+//! sub-machines that are all active at once. For each such state this fragment
+//! emits a handler that broadcasts the event to every region and merges their
+//! responses through [`dispatch_parallel`](statig::awaitable::dispatch_parallel),
+//! which runs every region's exit actions when a transition wins.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::analyze::{Handler, Machine};
+
+/// Emit the parallel-dispatch handlers for every state that declares regions.
+pub fn emit(machine: &Machine) -> TokenStream {
+    let ident = &machine.ident;
+
+    let handlers = machine
+        .states
+        .iter()
+        .filter(|state| !state.parallel.is_empty())
+        .map(|state| emit_state(ident, state));
+
+    quote! {
+        #(#handlers)*
+    }
+}
+
+fn emit_state(ident: &syn::Ident, state: &Handler) -> TokenStream {
+    let name = &state.name;
+    let handler = format_ident!("{}_parallel_dispatch", name);
+
+    // Each region is stored as a sub-machine field named after the region on
+    // the shared storage; borrow them all as `Region` trait objects for the
+    // broadcast. A fixed-size array keeps this `no_std`-friendly — no `Vec`.
+    let regions = state.parallel.iter();
+    let count = state.parallel.len();
+
+    quote! {
+        impl #ident {
+            /// Broadcast the event to the orthogonal regions of the
+            #[doc = concat!("`", stringify!(#name), "` state and merge their responses.")]
+            fn #handler<'a>(
+                &'a mut self,
+                event: &'a <Self as ::statig::IntoStateMachine>::Event<'_>,
+            ) -> ::futures::future::LocalBoxFuture<
+                'a,
+                ::statig::Response<<Self as ::statig::IntoStateMachine>::State>,
+            > {
+                ::futures::future::FutureExt::boxed_local(async move {
+                    let mut regions: [&mut dyn ::statig::awaitable::Region<Self>; #count] =
+                        [#(&mut self.#regions),*];
+                    ::statig::awaitable::dispatch_parallel(&mut regions, event).await
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Emission of the const depth/parent lookup tables.
+//!
+//! The superstate containment graph is fully known at macro-expansion time, so
+//! the macro assigns every state and superstate a stable index and precomputes
+//! its depth and parent index. The generated machine then resolves transitions
+//! with an integer walk over these tables
+//! ([`common_ancestor_depth_indexed`](statig::awaitable::common_ancestor_depth_indexed))
+//! instead of re-walking the superstate chain on every event.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// One node (state or superstate) in stable index order.
+pub struct Node {
+    /// The match pattern that identifies this variant, e.g. `State::LedOn { .. }`.
+    pub pattern: TokenStream,
+    /// Whether this node is a leaf state (as opposed to a superstate).
+    pub is_state: bool,
+    /// Depth of the node (the implicit `top` is depth `0`).
+    pub depth: usize,
+    /// Index of the parent node, or `None` for a root (emitted into the
+    /// parent table as `statig::awaitable::TOP`).
+    pub parent: Option<usize>,
+}
+
+/// The token fragments the macro splices into the generated impls.
+pub struct Tables {
+    /// The `STATE_DEPTHS`/`STATE_PARENTS` const items for the
+    /// `IntoStateMachine` impl.
+    pub consts: TokenStream,
+    /// The body of `State::index`, matching each leaf state to its index.
+    pub state_index: TokenStream,
+    /// The body of `Superstate::index`, matching each superstate to its index.
+    pub superstate_index: TokenStream,
+}
+
+/// Build the depth/parent const tables and the `index()` match bodies from the
+/// indexed hierarchy.
+pub fn emit(nodes: &[Node]) -> Tables {
+    let depths = nodes.iter().map(|node| node.depth);
+    let parents = nodes.iter().map(|node| match node.parent {
+        Some(index) => quote!(#index),
+        None => quote!(::statig::awaitable::TOP),
+    });
+
+    let consts = quote! {
+        const STATE_DEPTHS: &'static [usize] = &[#(#depths),*];
+        const STATE_PARENTS: &'static [usize] = &[#(#parents),*];
+    };
+
+    // `index()` maps each variant back to its position in the tables.
+    let index_arms = |want_state: bool| {
+        let arms = nodes
+            .iter()
+            .enumerate()
+            .filter(move |(_, node)| node.is_state == want_state)
+            .map(|(index, node)| {
+                let pattern = &node.pattern;
+                quote!(#pattern => #index,)
+            });
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    };
+
+    Tables {
+        consts,
+        state_index: index_arms(true),
+        superstate_index: index_arms(false),
+    }
+}
@@ -0,0 +1,10 @@
+//! Awaitable (async) state machine implementation.
+
+mod state;
+mod state_machine;
+mod superstate;
+
+pub use crate::lca::{common_ancestor_depth_indexed, TOP};
+pub use state::{State, StateExt};
+pub use state_machine::{StateMachine, DEFAULT_STACK_DEPTH};
+pub use superstate::{dispatch_parallel, Region, Superstate, SuperstateExt};
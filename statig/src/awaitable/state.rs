@@ -0,0 +1,147 @@
+use futures::future::{FutureExt, LocalBoxFuture};
+
+use crate::awaitable::Superstate;
+use crate::awaitable::SuperstateExt;
+use crate::IntoStateMachine;
+use crate::Response;
+use crate::StateOrSuperstate;
+
+/// An enum that represents the leaf states of the state machine.
+pub trait State<M>
+where
+    M: IntoStateMachine,
+{
+    /// Call the handler for the current state.
+    async fn call_handler(
+        &mut self,
+        shared_storage: &mut M,
+        event: &M::Event<'_>,
+        context: &mut M::Context<'_>,
+    ) -> Response<M::State>;
+
+    /// Call the entry action for the current state.
+    async fn call_entry_action(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>);
+
+    /// Call the exit action for the current state.
+    async fn call_exit_action(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>);
+
+    /// Return the superstate of the current state, if there is one.
+    fn superstate(&mut self) -> Option<M::Superstate<'_>> {
+        None
+    }
+
+    /// The stable index of this state in the macro-emitted depth/parent
+    /// tables ([`IntoStateMachine::STATE_DEPTHS`] /
+    /// [`IntoStateMachine::STATE_PARENTS`]).
+    fn index(&self) -> usize {
+        0
+    }
+}
+
+/// Extensions for the [`State`] trait.
+pub trait StateExt<M>: State<M>
+where
+    M: IntoStateMachine + Send + Sync,
+    M::State: Send + Sync,
+    for<'b> M::Superstate<'b>: Superstate<M> + Send + Sync,
+{
+    /// Get the depth of the current state.
+    fn depth(&mut self) -> usize {
+        match self.superstate() {
+            Some(mut superstate) => superstate.depth() + 1,
+            None => 1,
+        }
+    }
+
+    /// Handle the given event in the current state.
+    fn handle<'c>(
+        &'c mut self,
+        shared_storage: &'c mut M,
+        event: &'c M::Event<'_>,
+        context: &'c mut M::Context<'_>,
+    ) -> LocalBoxFuture<'c, Response<M::State>>
+    where
+        Self: Sized + Send + Sync,
+    {
+        async move {
+            let response = self.call_handler(shared_storage, event, context).await;
+
+            match response {
+                Response::Handled => Response::Handled,
+                Response::Super => match self.superstate() {
+                    Some(mut superstate) => {
+                        M::ON_DISPATCH(
+                            shared_storage,
+                            StateOrSuperstate::Superstate(&superstate),
+                            event,
+                        );
+
+                        superstate.handle(shared_storage, event, context).await
+                    }
+                    None => Response::Super,
+                },
+                Response::Transition(state) => Response::Transition(state),
+                Response::Push(state) => Response::Push(state),
+                Response::Pop => Response::Pop,
+            }
+        }
+        .boxed_local()
+    }
+
+    /// Climb a given amount of levels and execute the entry actions while going
+    /// back down to the current state.
+    fn enter<'a>(
+        &'a mut self,
+        shared_storage: &'a mut M,
+        context: &'a mut M::Context<'_>,
+        mut levels: usize,
+    ) -> LocalBoxFuture<'a, ()> {
+        async move {
+            match levels {
+                0 => (),
+                1 => self.call_entry_action(shared_storage, context).await,
+                _ => {
+                    if let Some(mut superstate) = self.superstate() {
+                        levels -= 1;
+                        superstate.enter(shared_storage, context, levels).await;
+                    }
+                    self.call_entry_action(shared_storage, context).await;
+                }
+            }
+        }
+        .boxed_local()
+    }
+
+    /// Climb a given amount of levels and execute the exit actions while going
+    /// up to a certain superstate.
+    fn exit<'a>(
+        &'a mut self,
+        shared_storage: &'a mut M,
+        context: &'a mut M::Context<'_>,
+        mut levels: usize,
+    ) -> LocalBoxFuture<'a, ()> {
+        async move {
+            match levels {
+                0 => (),
+                1 => self.call_exit_action(shared_storage, context).await,
+                _ => {
+                    self.call_exit_action(shared_storage, context).await;
+                    if let Some(mut superstate) = self.superstate() {
+                        levels -= 1;
+                        superstate.exit(shared_storage, context, levels).await;
+                    }
+                }
+            }
+        }
+        .boxed_local()
+    }
+}
+
+impl<T, M> StateExt<M> for T
+where
+    T: State<M>,
+    M: IntoStateMachine + Send + Sync,
+    M::State: Send + Sync,
+    for<'b> M::Superstate<'b>: Superstate<M> + Send + Sync,
+{
+}
@@ -0,0 +1,183 @@
+use futures::future::{FutureExt, LocalBoxFuture};
+
+use crate::awaitable::{common_ancestor_depth_indexed, Region, State, StateExt, Superstate};
+use crate::stack::StateStack;
+use crate::IntoStateMachine;
+use crate::Response;
+
+/// The default depth of the pushdown history stack.
+pub const DEFAULT_STACK_DEPTH: usize = 4;
+
+/// A running state machine with an awaitable (async) driver.
+///
+/// `N` bounds the depth of the pushdown history used by [`Response::Push`] /
+/// [`Response::Pop`]; it defaults to [`DEFAULT_STACK_DEPTH`] so most users never
+/// set it, while `no_std` targets with tight memory can shrink it.
+pub struct StateMachine<M, const N: usize = DEFAULT_STACK_DEPTH>
+where
+    M: IntoStateMachine,
+{
+    inner: M,
+    state: M::State,
+    stack: StateStack<M::State, N>,
+}
+
+impl<M, const N: usize> StateMachine<M, N>
+where
+    M: IntoStateMachine + Send + Sync,
+    M::State: State<M> + Send + Sync,
+    for<'b> M::Superstate<'b>: Superstate<M> + Send + Sync,
+{
+    /// Create the machine in its initial state. Call [`init_with_context`] to
+    /// run the entry actions down to the initial state before dispatching.
+    ///
+    /// [`init_with_context`]: StateMachine::init_with_context
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            state: M::INITIAL,
+            stack: StateStack::new(),
+        }
+    }
+
+    /// Run the entry actions from `top` down to the initial state.
+    pub async fn init_with_context(&mut self, context: &mut M::Context<'_>) {
+        let levels = Self::depth(&self.state);
+        self.state.enter(&mut self.inner, context, levels).await;
+    }
+
+    /// Depth of a state, read from the macro-emitted table.
+    fn depth(state: &M::State) -> usize {
+        M::STATE_DEPTHS[state.index()]
+    }
+
+    /// Depth of the common ancestor of two states, via the integer-walk LCA
+    /// over the macro-emitted tables — no per-transition superstate rebuilding.
+    fn common(source: &M::State, target: &M::State) -> usize {
+        common_ancestor_depth_indexed(
+            source.index(),
+            target.index(),
+            M::STATE_DEPTHS,
+            M::STATE_PARENTS,
+        )
+    }
+
+    /// Dispatch an event, applying the resulting response.
+    pub async fn handle_with_context(
+        &mut self,
+        event: &M::Event<'_>,
+        context: &mut M::Context<'_>,
+    ) {
+        let response = self.state.handle(&mut self.inner, event, context).await;
+
+        match response {
+            Response::Handled | Response::Super => {}
+            Response::Transition(target) => self.transition(target, context).await,
+            Response::Push(target) => self.push(target, context).await,
+            Response::Pop => self.pop(context).await,
+        }
+    }
+
+    /// Transition to `target`, running the exit actions up to the common
+    /// ancestor and the entry actions back down to the target.
+    async fn transition(&mut self, mut target: M::State, context: &mut M::Context<'_>) {
+        let common = Self::common(&self.state, &target);
+        let exit_levels = Self::depth(&self.state) - common;
+        let enter_levels = Self::depth(&target) - common;
+
+        self.state.exit(&mut self.inner, context, exit_levels).await;
+        M::ON_TRANSITION(&mut self.inner, &self.state, &target);
+        self.state = target;
+        self.state.enter(&mut self.inner, context, enter_levels).await;
+    }
+
+    /// Suspend the current state on the stack and transition down to `target`
+    /// *without* running the current state's exit actions. A `Push` onto a
+    /// full stack is a no-op: the machine stays in the current state.
+    async fn push(&mut self, mut target: M::State, context: &mut M::Context<'_>) {
+        if self.stack.len() == N {
+            return;
+        }
+
+        let common = Self::common(&self.state, &target);
+        let enter_levels = Self::depth(&target) - common;
+
+        M::ON_PAUSE(&mut self.inner, &self.state, &target);
+        target.enter(&mut self.inner, context, enter_levels).await;
+        let paused = core::mem::replace(&mut self.state, target);
+        self.stack.push(paused);
+    }
+
+    /// Restore the state on top of the stack, running the current state's exit
+    /// actions but *not* the restored state's entry actions. A `Pop` with an
+    /// empty stack is a defined no-op.
+    async fn pop(&mut self, context: &mut M::Context<'_>) {
+        let restored = match self.stack.pop() {
+            Some(restored) => restored,
+            None => return,
+        };
+
+        let common = Self::common(&self.state, &restored);
+        let exit_levels = Self::depth(&self.state) - common;
+
+        M::ON_RESUME(&mut self.inner, &self.state, &restored);
+        self.state.exit(&mut self.inner, context, exit_levels).await;
+        self.state = restored;
+    }
+
+    /// A shared reference to the current state.
+    pub fn state(&self) -> &M::State {
+        &self.state
+    }
+
+    /// The number of states currently suspended on the pushdown stack.
+    pub fn suspended(&self) -> usize {
+        self.stack.len()
+    }
+}
+
+/// A sub-machine is an orthogonal [`Region`] of its parent: dispatching reports
+/// the current state's response (the driver of the hosting parallel state
+/// resolves any transition), and exiting runs the region's exit actions up to
+/// `top`. Regions thread no context, matching the parallel-state lowering.
+impl<M, const N: usize> Region<M> for StateMachine<M, N>
+where
+    M: IntoStateMachine + Send + Sync,
+    for<'a> M: IntoStateMachine<Context<'a> = ()>,
+    M::State: State<M> + Send + Sync,
+    for<'b> M::Superstate<'b>: Superstate<M> + Send + Sync,
+{
+    fn dispatch<'a>(
+        &'a mut self,
+        event: &'a M::Event<'_>,
+    ) -> LocalBoxFuture<'a, Response<M::State>> {
+        async move { self.state.handle(&mut self.inner, event, &mut ()).await }.boxed_local()
+    }
+
+    fn exit(&mut self) -> LocalBoxFuture<'_, ()> {
+        async move {
+            let levels = Self::depth(&self.state);
+            self.state.exit(&mut self.inner, &mut (), levels).await;
+        }
+        .boxed_local()
+    }
+}
+
+/// Convenience methods for machines that thread no context.
+impl<M, const N: usize> StateMachine<M, N>
+where
+    M: IntoStateMachine + Send + Sync,
+    for<'a> M: IntoStateMachine<Context<'a> = ()>,
+    M::State: State<M> + Send + Sync,
+    for<'b> M::Superstate<'b>: Superstate<M> + Send + Sync,
+{
+    /// Run the entry actions from `top` down to the initial state.
+    pub async fn init(&mut self) {
+        self.init_with_context(&mut ()).await;
+    }
+
+    /// Dispatch an event, applying the resulting response.
+    pub async fn handle(&mut self, event: &M::Event<'_>) {
+        self.handle_with_context(event, &mut ()).await;
+    }
+}
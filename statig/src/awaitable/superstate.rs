@@ -1,6 +1,4 @@
-use core::cmp::Ordering;
-
-use futures::future::{FutureExt, LocalBoxFuture};
+use futures::future::{join_all, FutureExt, LocalBoxFuture};
 
 use crate::IntoStateMachine;
 use crate::Response;
@@ -34,6 +32,13 @@ where
     {
         None
     }
+
+    /// The stable index of this superstate in the macro-emitted
+    /// depth/parent tables ([`IntoStateMachine::STATE_DEPTHS`] /
+    /// [`IntoStateMachine::STATE_PARENTS`]).
+    fn index(&self) -> usize {
+        0
+    }
 }
 
 /// Extensions for `Superstate` trait.
@@ -44,19 +49,6 @@ where
     M::State: 'a + Send + Sync,
     for<'b> M::Superstate<'b>: Superstate<M> + Send + Sync,
 {
-    fn same_state(lhs: &M::Superstate<'_>, rhs: &M::Superstate<'_>) -> bool {
-        use core::mem::{discriminant, transmute, Discriminant};
-
-        // Generic associated types are invariant over any lifetime arguments, so the
-        // compiler won't allow us to compare them directly. Instead we need to coerce them
-        // to have the same lifetime by transmuting them to the same type.
-
-        let lhs: Discriminant<M::Superstate<'_>> = unsafe { transmute(discriminant(lhs)) };
-        let rhs: Discriminant<M::Superstate<'_>> = unsafe { transmute(discriminant(rhs)) };
-
-        lhs == rhs
-    }
-
     /// Get the depth of the current superstate.
     fn depth(&mut self) -> usize {
         match self.superstate() {
@@ -65,30 +57,6 @@ where
         }
     }
 
-    /// Get the depth of the common ancestor of two states.
-    fn common_ancestor_depth(
-        mut source: M::Superstate<'_>,
-        mut target: M::Superstate<'_>,
-    ) -> usize {
-        match source.depth().cmp(&target.depth()) {
-            Ordering::Equal => match Self::same_state(&source, &target) {
-                true => source.depth(),
-                false => match (source.superstate(), target.superstate()) {
-                    (Some(source), Some(target)) => Self::common_ancestor_depth(source, target),
-                    _ => 0,
-                },
-            },
-            Ordering::Greater => match source.superstate() {
-                Some(superstate) => Self::common_ancestor_depth(superstate, target),
-                None => 0,
-            },
-            Ordering::Less => match target.superstate() {
-                Some(superstate) => Self::common_ancestor_depth(source, superstate),
-                None => 0,
-            },
-        }
-    }
-
     /// Handle the given event in the current superstate.
     fn handle<'c>(
         &'c mut self,
@@ -117,6 +85,13 @@ where
                     None => Response::Super,
                 },
                 Response::Transition(state) => Response::Transition(state),
+                // A push suspends the current state on the machine's stack and
+                // transitions down to `state`; like a transition it is resolved by
+                // the driver, so we simply propagate it upwards unchanged.
+                Response::Push(state) => Response::Push(state),
+                // A pop restores the top-of-stack state; it is likewise resolved by
+                // the driver and propagated unchanged here.
+                Response::Pop => Response::Pop,
             }
         }
         .boxed_local()
@@ -171,6 +146,83 @@ where
     }
 }
 
+/// One orthogonal region hosted by a parallel state.
+///
+/// A region is itself a sub-machine over the parent machine's event and state
+/// types. Every [`StateMachine`](crate::awaitable::StateMachine) implements this
+/// trait through a blanket impl, so the fields named by a
+/// `#[state(parallel = [...])]` attribute — which are sub-machines — satisfy the
+/// bound directly and can be driven uniformly by [`dispatch_parallel`].
+pub trait Region<M>
+where
+    M: IntoStateMachine,
+{
+    /// Dispatch the event to this region and return its response.
+    fn dispatch<'a>(
+        &'a mut self,
+        event: &'a M::Event<'_>,
+    ) -> LocalBoxFuture<'a, Response<M::State>>;
+
+    /// Run this region's exit actions because the hosting parallel state is
+    /// being left.
+    fn exit(&mut self) -> LocalBoxFuture<'_, ()>;
+}
+
+/// Broadcast an event to several orthogonal regions concurrently and merge their
+/// responses into a single one.
+///
+/// Orthogonal (parallel) regions are all active simultaneously and each receive
+/// every dispatched event. Their responses are combined with the following
+/// precedence:
+///
+/// - if any region requests a [`Response::Transition`] (or [`Response::Push`] /
+///   [`Response::Pop`]) that response wins, **every** region's exit actions are
+///   run, and the parallel state is left,
+/// - otherwise the event is [`Response::Handled`] if any region handled it,
+/// - otherwise it is left as [`Response::Super`] so it is deferred to the
+///   superstate hosting the regions.
+///
+/// Each region owns its own sub-machine state, so the dispatch futures borrow
+/// disjoint regions and are polled together via [`join_all`] rather than
+/// strictly sequentially.
+pub async fn dispatch_parallel<M>(
+    regions: &mut [&mut dyn Region<M>],
+    event: &M::Event<'_>,
+) -> Response<M::State>
+where
+    M: IntoStateMachine,
+{
+    let mut winner = None;
+    let mut handled = false;
+
+    for response in join_all(regions.iter_mut().map(|region| region.dispatch(event))).await {
+        match response {
+            Response::Transition(state) => winner.get_or_insert(Response::Transition(state)),
+            Response::Push(state) => winner.get_or_insert(Response::Push(state)),
+            Response::Pop => winner.get_or_insert(Response::Pop),
+            Response::Handled => {
+                handled = true;
+                continue;
+            }
+            Response::Super => continue,
+        };
+    }
+
+    if let Some(response) = winner {
+        // A region requested to leave the composite: exit every region before
+        // the parallel state itself is exited by the driver.
+        for region in regions.iter_mut() {
+            region.exit().await;
+        }
+        return response;
+    }
+
+    match handled {
+        true => Response::Handled,
+        false => Response::Super,
+    }
+}
+
 /// When no superstates are required, the user can pass the [`()`](unit) type.
 impl<'a, M> Superstate<M> for ()
 where
@@ -0,0 +1,9 @@
+//! Blocking (synchronous) state machine implementation.
+
+mod state;
+mod state_machine;
+mod superstate;
+
+pub use state::{State, StateExt};
+pub use state_machine::{StateMachine, DEFAULT_STACK_DEPTH};
+pub use superstate::{Superstate, SuperstateExt};
@@ -0,0 +1,110 @@
+use crate::blocking::Superstate;
+use crate::blocking::SuperstateExt;
+use crate::IntoStateMachine;
+use crate::Response;
+use crate::StateOrSuperstate;
+
+/// An enum that represents the leaf states of the state machine.
+pub trait State<M>
+where
+    M: IntoStateMachine,
+{
+    /// Call the handler for the current state.
+    fn call_handler(
+        &mut self,
+        shared_storage: &mut M,
+        event: &M::Event<'_>,
+        context: &mut M::Context<'_>,
+    ) -> Response<M::State>;
+
+    /// Call the entry action for the current state.
+    fn call_entry_action(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>);
+
+    /// Call the exit action for the current state.
+    fn call_exit_action(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>);
+
+    /// Return the superstate of the current state, if there is one.
+    fn superstate(&mut self) -> Option<M::Superstate<'_>> {
+        None
+    }
+
+    /// The stable index of this state in the macro-emitted depth/parent tables.
+    fn index(&self) -> usize {
+        0
+    }
+}
+
+/// Extensions for the [`State`] trait.
+pub trait StateExt<M>: State<M>
+where
+    M: IntoStateMachine,
+{
+    /// Handle the given event in the current state.
+    fn handle(
+        &mut self,
+        shared_storage: &mut M,
+        event: &M::Event<'_>,
+        context: &mut M::Context<'_>,
+    ) -> Response<M::State>
+    where
+        Self: Sized,
+    {
+        match self.call_handler(shared_storage, event, context) {
+            Response::Handled => Response::Handled,
+            Response::Super => match self.superstate() {
+                Some(mut superstate) => {
+                    M::ON_DISPATCH(
+                        shared_storage,
+                        StateOrSuperstate::Superstate(&superstate),
+                        event,
+                    );
+
+                    superstate.handle(shared_storage, event, context)
+                }
+                None => Response::Super,
+            },
+            Response::Transition(state) => Response::Transition(state),
+            Response::Push(state) => Response::Push(state),
+            Response::Pop => Response::Pop,
+        }
+    }
+
+    /// Climb a given amount of levels and execute the entry actions while going
+    /// back down to the current state.
+    fn enter(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>, mut levels: usize) {
+        match levels {
+            0 => (),
+            1 => self.call_entry_action(shared_storage, context),
+            _ => {
+                if let Some(mut superstate) = self.superstate() {
+                    levels -= 1;
+                    superstate.enter(shared_storage, context, levels);
+                }
+                self.call_entry_action(shared_storage, context);
+            }
+        }
+    }
+
+    /// Climb a given amount of levels and execute the exit actions while going
+    /// up to a certain superstate.
+    fn exit(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>, mut levels: usize) {
+        match levels {
+            0 => (),
+            1 => self.call_exit_action(shared_storage, context),
+            _ => {
+                self.call_exit_action(shared_storage, context);
+                if let Some(mut superstate) = self.superstate() {
+                    levels -= 1;
+                    superstate.exit(shared_storage, context, levels);
+                }
+            }
+        }
+    }
+}
+
+impl<T, M> StateExt<M> for T
+where
+    T: State<M>,
+    M: IntoStateMachine,
+{
+}
@@ -0,0 +1,131 @@
+use crate::IntoStateMachine;
+use crate::Response;
+use crate::StateOrSuperstate;
+
+/// An enum that represents the superstates of the state machine.
+pub trait Superstate<M>
+where
+    M: IntoStateMachine,
+{
+    /// Call the handler for the current superstate.
+    fn call_handler(
+        &mut self,
+        shared_storage: &mut M,
+        event: &M::Event<'_>,
+        context: &mut M::Context<'_>,
+    ) -> Response<M::State>;
+
+    #[allow(unused)]
+    /// Call the entry action for the current superstate.
+    fn call_entry_action(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>);
+
+    #[allow(unused)]
+    /// Call the exit action for the current superstate.
+    fn call_exit_action(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>);
+
+    /// Return the superstate of the current superstate, if there is one.
+    fn superstate(&mut self) -> Option<M::Superstate<'_>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// The stable index of this superstate in the macro-emitted depth/parent
+    /// tables.
+    fn index(&self) -> usize {
+        0
+    }
+}
+
+/// Extensions for the [`Superstate`] trait.
+pub trait SuperstateExt<M>: Superstate<M>
+where
+    Self: Sized,
+    M: IntoStateMachine,
+{
+    /// Handle the given event in the current superstate.
+    fn handle(
+        &mut self,
+        shared_storage: &mut M,
+        event: &M::Event<'_>,
+        context: &mut M::Context<'_>,
+    ) -> Response<M::State> {
+        match self.call_handler(shared_storage, event, context) {
+            Response::Handled => Response::Handled,
+            Response::Super => match self.superstate() {
+                Some(mut superstate) => {
+                    M::ON_DISPATCH(
+                        shared_storage,
+                        StateOrSuperstate::Superstate(&superstate),
+                        event,
+                    );
+
+                    superstate.handle(shared_storage, event, context)
+                }
+                None => Response::Super,
+            },
+            Response::Transition(state) => Response::Transition(state),
+            Response::Push(state) => Response::Push(state),
+            Response::Pop => Response::Pop,
+        }
+    }
+
+    /// Starting from the current superstate, climb a given amount of levels and
+    /// execute the entry actions while going back down to the current superstate.
+    fn enter(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>, mut levels: usize) {
+        match levels {
+            0 => (),
+            1 => self.call_entry_action(shared_storage, context),
+            _ => {
+                if let Some(mut superstate) = self.superstate() {
+                    levels -= 1;
+                    superstate.enter(shared_storage, context, levels);
+                }
+                self.call_entry_action(shared_storage, context);
+            }
+        }
+    }
+
+    /// Starting from the current superstate, climb a given amount of levels and
+    /// execute the exit actions while going up to a certain superstate.
+    fn exit(&mut self, shared_storage: &mut M, context: &mut M::Context<'_>, mut levels: usize) {
+        match levels {
+            0 => (),
+            1 => self.call_exit_action(shared_storage, context),
+            _ => {
+                self.call_exit_action(shared_storage, context);
+                if let Some(mut superstate) = self.superstate() {
+                    levels -= 1;
+                    superstate.exit(shared_storage, context, levels);
+                }
+            }
+        }
+    }
+}
+
+/// When no superstates are required, the user can pass the [`()`](unit) type.
+impl<M> Superstate<M> for ()
+where
+    M: IntoStateMachine,
+{
+    fn call_handler(
+        &mut self,
+        _: &mut M,
+        _: &M::Event<'_>,
+        _: &mut M::Context<'_>,
+    ) -> Response<M::State> {
+        Response::Handled
+    }
+
+    fn call_entry_action(&mut self, _: &mut M, _: &mut M::Context<'_>) {}
+
+    fn call_exit_action(&mut self, _: &mut M, _: &mut M::Context<'_>) {}
+}
+
+impl<T, M> SuperstateExt<M> for T
+where
+    T: Superstate<M>,
+    M: IntoStateMachine,
+{
+}
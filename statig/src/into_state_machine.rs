@@ -0,0 +1,63 @@
+use crate::awaitable::Superstate;
+
+/// Trait implemented by the shared storage type to describe its state machine.
+///
+/// The `state_machine` macro generates the `State`/`Superstate` enums and
+/// implements this trait; the associated constants provide the optional
+/// lifecycle hooks, each defaulting to a no-op.
+pub trait IntoStateMachine
+where
+    Self: Sized,
+{
+    /// The enum of leaf states.
+    type State;
+
+    /// The enum of superstates, borrowing from the shared storage.
+    type Superstate<'a>: Superstate<Self>
+    where
+        Self: 'a;
+
+    /// The event type dispatched to the machine.
+    type Event<'a>;
+
+    /// The context threaded through every handler.
+    type Context<'a>;
+
+    /// The initial state entered by [`init`](crate::awaitable::StateMachine::init).
+    const INITIAL: Self::State;
+
+    /// Precomputed depth of every state and superstate, indexed by the stable
+    /// index assigned at macro-expansion time. Lets transitions compute
+    /// enter/exit levels without re-walking the superstate chain.
+    const STATE_DEPTHS: &'static [usize];
+
+    /// Precomputed parent index of every state and superstate (with
+    /// [`TOP`](crate::awaitable::TOP) for a root), used by the integer-walk LCA.
+    const STATE_PARENTS: &'static [usize];
+
+    /// Called on every transition with the source and target state.
+    const ON_TRANSITION: fn(&mut Self, &Self::State, &Self::State) = |_, _, _| {};
+
+    /// Called on every dispatch with the state or superstate handling the event.
+    const ON_DISPATCH: fn(&mut Self, StateOrSuperstate<Self>, &Self::Event<'_>) = |_, _, _| {};
+
+    /// Called when a state is suspended by a [`Push`](crate::Response::Push),
+    /// with the state being paused and the state being entered.
+    const ON_PAUSE: fn(&mut Self, &Self::State, &Self::State) = |_, _, _| {};
+
+    /// Called when a state is restored by a [`Pop`](crate::Response::Pop), with
+    /// the state being exited and the state being resumed.
+    const ON_RESUME: fn(&mut Self, &Self::State, &Self::State) = |_, _, _| {};
+}
+
+/// A reference to either a state or a superstate, passed to
+/// [`ON_DISPATCH`](IntoStateMachine::ON_DISPATCH).
+pub enum StateOrSuperstate<'a, M>
+where
+    M: IntoStateMachine,
+{
+    /// A leaf state.
+    State(&'a M::State),
+    /// A superstate.
+    Superstate(&'a M::Superstate<'a>),
+}
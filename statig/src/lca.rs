@@ -0,0 +1,48 @@
+//! Lowest-common-ancestor computation over the precomputed hierarchy tables.
+//!
+//! Both the blocking and awaitable drivers resolve transitions with an integer
+//! walk over the stable index/depth/parent tables the `state_machine` macro
+//! emits, rather than re-walking the superstate chain on every event.
+
+use core::cmp::Ordering;
+
+/// Sentinel parent index for a root node whose only parent is the implicit
+/// `top` state. Emitted into the macro's parent table.
+pub const TOP: usize = usize::MAX;
+
+/// Compute the depth of the common ancestor of two states from the precomputed
+/// parent-index and depth tables emitted by the `state_machine` macro.
+///
+/// Each state and superstate is assigned a stable index at macro-expansion time
+/// together with its depth and the index of its parent ([`TOP`] for a root).
+/// This finds the lowest common ancestor with a plain integer walk — climb the
+/// deeper side until the depths match, then climb both in lockstep until the
+/// indices are equal — instead of repeatedly reconstructing `M::Superstate`
+/// values and recursing over them. Two chains that share only the implicit
+/// `top` return depth `0`.
+pub fn common_ancestor_depth_indexed(
+    mut source: usize,
+    mut target: usize,
+    depths: &[usize],
+    parents: &[usize],
+) -> usize {
+    while source != target {
+        // Either chain reaching `top` means the only shared ancestor is `top`.
+        if source == TOP || target == TOP {
+            return 0;
+        }
+        match depths[source].cmp(&depths[target]) {
+            Ordering::Greater => source = parents[source],
+            Ordering::Less => target = parents[target],
+            Ordering::Equal => {
+                source = parents[source];
+                target = parents[target];
+            }
+        }
+    }
+
+    match source {
+        TOP => 0,
+        index => depths[index],
+    }
+}
@@ -0,0 +1,26 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod awaitable;
+pub mod blocking;
+mod into_state_machine;
+mod lca;
+mod response;
+mod stack;
+
+#[cfg(feature = "test")]
+pub mod test;
+
+pub use into_state_machine::{IntoStateMachine, StateOrSuperstate};
+pub use lca::{common_ancestor_depth_indexed, TOP};
+pub use response::Response;
+
+/// Commonly used types, re-exported for convenience.
+pub mod prelude {
+    pub use crate::awaitable::StateMachine;
+    pub use crate::IntoStateMachine;
+    pub use crate::Response::{self, *};
+    pub use crate::StateOrSuperstate;
+}
@@ -0,0 +1,18 @@
+/// The response returned by a state or superstate handler.
+pub enum Response<S> {
+    /// The event has been handled.
+    Handled,
+    /// Defer the event to the superstate.
+    Super,
+    /// Transition to the given state.
+    Transition(S),
+    /// Suspend the current state on the machine's stack and transition down to
+    /// the given state. The suspended state is left on the stack *without*
+    /// running its exit actions and is restored by a later [`Response::Pop`].
+    /// A `Push` onto a full stack is a no-op.
+    Push(S),
+    /// Restore the state on top of the machine's stack, running the exit actions
+    /// of the current state but *not* the entry actions of the restored state
+    /// (it was only paused). A `Pop` with an empty stack is a no-op.
+    Pop,
+}
@@ -0,0 +1,69 @@
+//! Bounded state history stack used for pushdown (`Push`/`Pop`) transitions.
+//!
+//! On `no_std` targets we cannot grow a history without bound, so the stack has
+//! a fixed, compile-time capacity `N`. Pushing onto a full stack is a defined
+//! no-op that leaves the existing history intact and reports the rejection, so
+//! a later `Pop` never resumes the wrong state.
+
+/// A fixed-capacity stack of suspended states.
+///
+/// The capacity `N` bounds the memory used by pushdown transitions on
+/// `no_std` targets. It is generic over the stored state `S`.
+pub struct StateStack<S, const N: usize> {
+    buffer: [Option<S>; N],
+    len: usize,
+}
+
+impl<S, const N: usize> StateStack<S, N> {
+    const INIT: Option<S> = None;
+
+    /// Create an empty stack.
+    pub const fn new() -> Self {
+        Self {
+            buffer: [Self::INIT; N],
+            len: 0,
+        }
+    }
+
+    /// Push a state onto the stack, returning whether it was stored.
+    ///
+    /// The capacity `N` is a hard bound: a push onto a full stack is rejected as
+    /// a no-op and returns `false`, leaving the existing `N` entries untouched.
+    /// Evicting the oldest entry would let a later `Pop` resume the wrong state,
+    /// so overflowing pushes are dropped rather than silently reordering the
+    /// history. Callers that must not lose a suspended state should size `N`
+    /// accordingly.
+    pub fn push(&mut self, state: S) -> bool {
+        if self.len == N {
+            return false;
+        }
+        self.buffer[self.len] = Some(state);
+        self.len += 1;
+        true
+    }
+
+    /// Pop the top state off the stack, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<S> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.buffer[self.len].take()
+    }
+
+    /// The number of suspended states currently on the stack.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the stack holds no suspended states.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<S, const N: usize> Default for StateStack<S, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}